@@ -20,7 +20,10 @@
 //!
 //! fn main() {
 //!     let set = vec![1, 2, 3, 4, 5];
-//!     let mut capped_set = CappedMultiset::new(None);
+//!     let mut capped_set: CappedMultiset<usize> = CappedMultiset::new(None);
+//!     for (elem, count) in set.into_iter().enumerate() {
+//!         capped_set.insert_multiple(elem, count);
+//!     }
 //!     assert_eq!(capped_set.sum(), 15);
 //!     capped_set.set_cap(Some(1));
 //!     assert_eq!(capped_set.sum(), 5);
@@ -31,31 +34,28 @@
 
 // Clippy Lints
 #![allow(unknown_lints)]
-#![warn(cast_possible_truncation)]
-#![warn(cast_possible_wrap)]
-#![warn(cast_precision_loss)]
-#![warn(cast_sign_loss)]
-#![warn(empty_enum)]
-#![warn(enum_glob_use)]
-#![warn(filter_map)]
-#![warn(if_not_else)]
-#![warn(indexing_slicing)]
-#![warn(invalid_upcast_comparisons)]
-#![warn(items_after_statements)]
-#![warn(missing_docs_in_private_items)]
-#![warn(mut_mut)]
-#![warn(nonminimal_bool)]
-#![warn(option_map_unwrap_or)]
-#![warn(option_map_unwrap_or_else)]
-#![warn(pub_enum_variant_names)]
-#![warn(result_unwrap_used)]
-#![warn(shadow_reuse)]
-#![warn(shadow_same)]
-#![warn(shadow_unrelated)]
-#![warn(similar_names)]
-#![warn(single_match_else)]
-#![warn(stutter)]
-#![warn(wrong_pub_self_convention)]
+#![warn(clippy::cast_possible_truncation)]
+#![warn(clippy::cast_possible_wrap)]
+#![warn(clippy::cast_precision_loss)]
+#![warn(clippy::cast_sign_loss)]
+#![warn(clippy::empty_enums)]
+#![warn(clippy::enum_glob_use)]
+#![warn(clippy::manual_filter_map)]
+#![warn(clippy::if_not_else)]
+#![warn(clippy::indexing_slicing)]
+#![warn(clippy::invalid_upcast_comparisons)]
+#![warn(clippy::items_after_statements)]
+#![warn(clippy::missing_docs_in_private_items)]
+#![warn(clippy::mut_mut)]
+#![warn(clippy::nonminimal_bool)]
+#![warn(clippy::map_unwrap_or)]
+#![warn(clippy::unwrap_used)]
+#![warn(clippy::shadow_reuse)]
+#![warn(clippy::shadow_same)]
+#![warn(clippy::shadow_unrelated)]
+#![warn(clippy::similar_names)]
+#![warn(clippy::single_match_else)]
+#![warn(clippy::module_name_repetitions)]
 
 #![warn(missing_docs,
         missing_debug_implementations,
@@ -66,7 +66,8 @@
         unused_import_braces, unused_qualifications)]
 
 use std::collections::BTreeMap;
-use std::collections::btree_map::Entry;
+use std::collections::btree_map::{self, Entry};
+use std::iter::FromIterator;
 
 
 /// A `CappedMultiset` structure is a data structure similar to a multiset with the key distinction
@@ -99,7 +100,7 @@ where
     pub fn new(cap: Option<usize>) -> Self {
         CappedMultiset {
             elements: BTreeMap::new(),
-            cap: cap,
+            cap,
         }
     }
 
@@ -189,6 +190,312 @@ where
         self.capped_val(count)
     }
 
+    /// Removes a single occurrence of `elem` from the Multiset.
+    /// This is equivalent to `remove_multiple(elem, 1)`. See
+    /// [`remove_multiple`](#method.remove_multiple) for details on how the
+    /// `cap` is handled.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// mset.insert_multiple(0, 3);
+    /// mset.remove(&0);
+    /// assert_eq!(2, mset.count_of(0));
+    /// ```
+    pub fn remove(&mut self, elem: &U) -> usize {
+        self.remove_multiple(elem, 1)
+    }
+
+    /// Removes up to `n` occurrences of `elem` from the Multiset, using
+    /// saturating arithmetic on the raw stored count. If the count reaches
+    /// `0`, the entry for `elem` is deleted from the underlying map entirely
+    /// so that iteration and length stay correct. Returns the number of
+    /// occurrences actually removed.
+    ///
+    /// This operation mutates the real stored data and hence ignores the
+    /// current `cap`; to remove only as many occurrences as are currently
+    /// visible through the `cap`, use [`take`](#method.take) instead.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(2));
+    /// mset.insert_multiple(0, 5);
+    /// let removed = mset.remove_multiple(&0, 3);
+    /// assert_eq!(3, removed);
+    /// assert_eq!(2, mset.count_of(0));
+    /// let removed = mset.remove_multiple(&0, 10);
+    /// assert_eq!(2, removed);
+    /// assert_eq!(0, mset.count_of(0));
+    /// ```
+    pub fn remove_multiple(&mut self, elem: &U, n: usize) -> usize {
+        let (removed, is_empty) = match self.elements.get_mut(elem) {
+            None => return 0,
+            Some(count) => {
+                let removed = std::cmp::min(*count, n);
+                *count -= removed;
+                (removed, *count == 0)
+            }
+        };
+        if is_empty {
+            self.elements.remove(elem);
+        }
+        removed
+    }
+
+    /// Removes up to `n` occurrences of `elem` from the Multiset, honoring
+    /// the current `cap`: at most `capped_val(count_of(elem))` occurrences
+    /// can ever be taken, since that is the visible count. Returns the
+    /// number of occurrences actually removed.
+    ///
+    /// Unlike [`remove_multiple`](#method.remove_multiple), which ignores
+    /// the `cap` because it mutates real data, `take` treats the `cap` as a
+    /// hard limit on what can be removed in a single call.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(2));
+    /// mset.insert_multiple(0, 5);
+    /// let taken = mset.take(&0, 10);
+    /// assert_eq!(2, taken);
+    /// assert_eq!(2, mset.count_of(0));
+    /// mset.set_cap(None);
+    /// assert_eq!(3, mset.count_of(0));
+    /// ```
+    pub fn take(&mut self, elem: &U, n: usize) -> usize {
+        let stored = self.elements.get(elem).map_or(0, |x| *x);
+        let visible = self.capped_val(stored);
+        let takeable = std::cmp::min(visible, n);
+        self.remove_multiple(elem, takeable)
+    }
+
+    /// Returns an iterator over `(&U, usize)` pairs, one per distinct
+    /// element, where the `usize` is the *capped* count. Does not mutate or
+    /// clone the stored data.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(2));
+    /// mset.insert_multiple(0, 5);
+    /// mset.insert_multiple(1, 1);
+    /// let pairs: Vec<(&u32, usize)> = mset.iter().collect();
+    /// assert_eq!(vec![(&0, 2), (&1, 1)], pairs);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, U> {
+        Iter {
+            inner: self.elements.iter(),
+            cap: self.cap,
+        }
+    }
+
+    /// Returns an iterator over the distinct elements of the Multiset,
+    /// irrespective of the current `cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// mset.insert_multiple(0, 5);
+    /// mset.insert_multiple(1, 1);
+    /// let keys: Vec<&u32> = mset.keys().collect();
+    /// assert_eq!(vec![&0, &1], keys);
+    /// ```
+    pub fn keys(&self) -> btree_map::Keys<'_, U, usize> {
+        self.elements.keys()
+    }
+
+    /// Returns the sum of `capped_val(count)` across all entries, i.e. the
+    /// total number of elements visible under the current `cap`. Uses
+    /// saturating addition so that an extremely large multiset cannot
+    /// overflow `usize`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(2));
+    /// mset.insert_multiple(0, 5);
+    /// mset.insert_multiple(1, 1);
+    /// assert_eq!(3, mset.sum());
+    /// ```
+    pub fn sum(&self) -> usize {
+        match self.cap {
+            None => self.elements
+                .values()
+                .fold(0, |acc, count| acc.saturating_add(*count)),
+            Some(cap) => self.capped_sum(cap),
+        }
+    }
+
+    /// Sums the raw stored counts, each clamped to `cap`, using saturating
+    /// addition.
+    fn capped_sum(&self, cap: usize) -> usize {
+        self.elements
+            .values()
+            .fold(0, |acc, count| acc.saturating_add(std::cmp::min(*count, cap)))
+    }
+
+    /// Returns the total multiplicity of the Multiset under the current
+    /// `cap`. This is an alias for [`sum`](#method.sum).
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// mset.insert_multiple(0, 5);
+    /// assert_eq!(5, mset.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.sum()
+    }
+
+    /// Returns `true` if the Multiset is empty under the current `cap`,
+    /// i.e. `self.len() == 0`. Note that this honors the `cap`: a
+    /// `CappedMultiset` with a `cap` of `Some(0)` is always empty by this
+    /// definition, even if it holds elements with a positive raw count.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// assert!(mset.is_empty());
+    /// mset.insert(0);
+    /// assert!(!mset.is_empty());
+    /// mset.set_cap(Some(0));
+    /// assert!(mset.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of distinct elements stored in the Multiset,
+    /// irrespective of the current `cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(1));
+    /// mset.insert_multiple(0, 5);
+    /// mset.insert_multiple(1, 5);
+    /// assert_eq!(2, mset.total_distinct());
+    /// ```
+    pub fn total_distinct(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns the largest capped multiplicity among all entries, or `0` if
+    /// the Multiset is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(3));
+    /// mset.insert_multiple(0, 1);
+    /// mset.insert_multiple(1, 10);
+    /// assert_eq!(3, mset.max_count());
+    /// ```
+    pub fn max_count(&self) -> usize {
+        self.elements
+            .values()
+            .map(|count| self.capped_val(*count))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Retains only the elements for which `f` returns `true`. `f` receives
+    /// each element along with its *capped* count, but since removal
+    /// deletes the real stored entry, once a key is dropped its raw count
+    /// is gone for good, not just hidden by the `cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(2));
+    /// mset.insert_multiple(0, 1);
+    /// mset.insert_multiple(1, 5);
+    /// mset.retain(|_, count| count >= 2);
+    /// assert_eq!(0, mset.count_of(0));
+    /// assert_eq!(2, mset.count_of(1));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&U, usize) -> bool,
+    {
+        let cap = self.cap;
+        let elements = std::mem::take(&mut self.elements);
+        self.elements = elements
+            .into_iter()
+            .filter(|&(ref elem, count)| {
+                let capped = match cap {
+                    None => count,
+                    Some(c) => std::cmp::min(count, c),
+                };
+                f(elem, capped)
+            })
+            .collect();
+    }
+
+    /// Removes every element for which `f` returns `true`, as in
+    /// [`retain`](#method.retain), and returns an iterator of the removed
+    /// `(element, capped count)` pairs. `f` receives each element's
+    /// *capped* count; the pairs yielded are likewise the capped counts,
+    /// not the raw stored counts that were deleted.
+    ///
+    /// Unlike the lazy `extract_if` added to std's `HashMap`/`HashSet`, the
+    /// matching entries here are removed from the Multiset as soon as this
+    /// method is called rather than while the returned iterator is driven;
+    /// draining a `BTreeMap` lazily without `unsafe` isn't possible, and
+    /// this crate does not use `unsafe`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(2));
+    /// mset.insert_multiple(0, 1);
+    /// mset.insert_multiple(1, 5);
+    /// let removed: Vec<(u32, usize)> = mset.extract_if(|_, count| count < 2).collect();
+    /// assert_eq!(vec![(0, 1)], removed);
+    /// assert_eq!(2, mset.count_of(1));
+    /// ```
+    pub fn extract_if<F>(&mut self, mut f: F) -> ExtractIf<U>
+    where
+        F: FnMut(&U, usize) -> bool,
+    {
+        let cap = self.cap;
+        let elements = std::mem::take(&mut self.elements);
+        let mut kept = BTreeMap::new();
+        let mut removed = Vec::new();
+        for (elem, count) in elements {
+            let capped = match cap {
+                None => count,
+                Some(c) => std::cmp::min(count, c),
+            };
+            if f(&elem, capped) {
+                removed.push((elem, capped));
+            } else {
+                kept.insert(elem, count);
+            }
+        }
+        self.elements = kept;
+        ExtractIf {
+            inner: removed.into_iter(),
+        }
+    }
+
     /// Return a value after honoring the current `cap`
     #[inline]
     fn capped_val(&self, value: usize) -> usize {
@@ -198,3 +505,468 @@ where
         }
     }
 }
+
+impl<U> CappedMultiset<U>
+where
+    U: Ord + Clone,
+{
+    /// Builds a new `CappedMultiset` from a raw element -> count map and a
+    /// `cap`. The resulting multiset carries the given `cap` and never
+    /// contains keys mapped to a count of `0`.
+    fn from_raw_counts(mut elements: BTreeMap<U, usize>, cap: Option<usize>) -> Self {
+        elements.retain(|_, count| *count != 0);
+        CappedMultiset {
+            elements,
+            cap,
+        }
+    }
+
+    /// Combines `self` and `other` key-by-key using `op` over their raw
+    /// (uncapped) counts, dropping any key whose resulting count is `0`.
+    /// The `cap` of the combined result is always `self.cap`, since the cap
+    /// is a non-lossy view and is applied on top of the combined raw counts
+    /// rather than during the combine.
+    fn combine_with<F>(&self, other: &Self, op: F) -> Self
+    where
+        F: Fn(usize, usize) -> usize,
+    {
+        let mut elements = BTreeMap::new();
+        for key in self.elements.keys().chain(other.elements.keys()) {
+            if elements.contains_key(key) {
+                continue;
+            }
+            let a = self.elements.get(key).map_or(0, |x| *x);
+            let b = other.elements.get(key).map_or(0, |x| *x);
+            let count = op(a, b);
+            if count != 0 {
+                elements.insert(key.clone(), count);
+            }
+        }
+        Self::from_raw_counts(elements, self.cap)
+    }
+
+    /// Combines `self` and `other` key-by-key using `op` over their raw
+    /// (uncapped) counts, writing the result back into `self.elements` and
+    /// dropping any key whose resulting count is `0`. `self.cap` is left
+    /// unchanged.
+    fn combine_with_mut<F>(&mut self, other: &Self, op: F)
+    where
+        F: Fn(usize, usize) -> usize,
+    {
+        let combined = self.combine_with(other, op);
+        self.elements = combined.elements;
+    }
+
+    /// Returns the union of `self` and `other`: the multiplicity of each key
+    /// in the result is `max(count_a, count_b)` over the raw stored counts.
+    /// The result carries `self.cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut a: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// a.insert_multiple(0, 3);
+    /// let mut b: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// b.insert_multiple(0, 5);
+    /// b.insert_multiple(1, 2);
+    /// let u = a.union(&b);
+    /// assert_eq!(5, u.count_of(0));
+    /// assert_eq!(2, u.count_of(1));
+    /// ```
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine_with(other, std::cmp::max)
+    }
+
+    /// In-place variant of [`union`](#method.union): mutates `self.elements`
+    /// to hold the union of `self` and `other`.
+    pub fn union_with(&mut self, other: &Self) {
+        self.combine_with_mut(other, std::cmp::max);
+    }
+
+    /// Returns the intersection of `self` and `other`: the multiplicity of
+    /// each key in the result is `min(count_a, count_b)` over the raw stored
+    /// counts. Keys whose resulting count is `0` are omitted. The result
+    /// carries `self.cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut a: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// a.insert_multiple(0, 3);
+    /// let mut b: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// b.insert_multiple(0, 5);
+    /// b.insert_multiple(1, 2);
+    /// let i = a.intersection(&b);
+    /// assert_eq!(3, i.count_of(0));
+    /// assert_eq!(0, i.count_of(1));
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine_with(other, std::cmp::min)
+    }
+
+    /// In-place variant of [`intersection`](#method.intersection): mutates
+    /// `self.elements` to hold the intersection of `self` and `other`.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.combine_with_mut(other, std::cmp::min);
+    }
+
+    /// Returns the additive union of `self` and `other`: the multiplicity of
+    /// each key in the result is `count_a + count_b` over the raw stored
+    /// counts. The result carries `self.cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut a: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// a.insert_multiple(0, 3);
+    /// let mut b: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// b.insert_multiple(0, 5);
+    /// let s = a.sum_with(&b);
+    /// assert_eq!(8, s.count_of(0));
+    /// ```
+    pub fn sum_with(&self, other: &Self) -> Self {
+        self.combine_with(other, |a, b| a + b)
+    }
+
+    /// In-place variant of [`sum_with`](#method.sum_with): mutates
+    /// `self.elements` so each key's count becomes `count_a + count_b`.
+    pub fn add_with(&mut self, other: &Self) {
+        self.combine_with_mut(other, |a, b| a + b);
+    }
+
+    /// Returns the difference of `self` and `other`: the multiplicity of
+    /// each key in the result is `count_a.saturating_sub(count_b)` over the
+    /// raw stored counts. Keys whose resulting count is `0` are omitted.
+    /// The result carries `self.cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut a: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// a.insert_multiple(0, 5);
+    /// let mut b: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// b.insert_multiple(0, 3);
+    /// let d = a.difference(&b);
+    /// assert_eq!(2, d.count_of(0));
+    /// ```
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine_with(other, |a, b| a.saturating_sub(b))
+    }
+
+    /// In-place variant of [`difference`](#method.difference): mutates
+    /// `self.elements` to hold the difference of `self` and `other`.
+    pub fn difference_with(&mut self, other: &Self) {
+        self.combine_with_mut(other, |a, b| a.saturating_sub(b));
+    }
+
+    /// Returns the symmetric difference of `self` and `other`: the
+    /// multiplicity of each key in the result is the absolute difference
+    /// between `count_a` and `count_b` over the raw stored counts. Keys
+    /// whose resulting count is `0` are omitted. The result carries
+    /// `self.cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut a: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// a.insert_multiple(0, 5);
+    /// let mut b: CappedMultiset<u32> = CappedMultiset::new(None);
+    /// b.insert_multiple(0, 3);
+    /// b.insert_multiple(1, 2);
+    /// let sd = a.symmetric_difference(&b);
+    /// assert_eq!(2, sd.count_of(0));
+    /// assert_eq!(2, sd.count_of(1));
+    /// ```
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.combine_with(other, |a, b| a.abs_diff(b))
+    }
+
+    /// In-place variant of [`symmetric_difference`](#method.symmetric_difference):
+    /// mutates `self.elements` to hold the symmetric difference of `self`
+    /// and `other`.
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        self.combine_with_mut(other, |a, b| a.abs_diff(b));
+    }
+
+    /// Returns a flattening iterator that yields each element cloned
+    /// `capped_val(count)` times, i.e. the elements the Multiset would hand
+    /// out if it were fully expanded under the current `cap`.
+    ///
+    /// # Example
+    /// ```
+    /// use capped_multiset::CappedMultiset;
+    ///
+    /// let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(2));
+    /// mset.insert_multiple(0, 3);
+    /// let expanded: Vec<u32> = mset.iter_expanded().collect();
+    /// assert_eq!(vec![0, 0], expanded);
+    /// ```
+    pub fn iter_expanded(&self) -> IterExpanded<'_, U> {
+        IterExpanded {
+            inner: self.elements.iter(),
+            cap: self.cap,
+            current: None,
+        }
+    }
+}
+
+impl<U> std::ops::BitOr for &CappedMultiset<U>
+where
+    U: Ord + Clone,
+{
+    type Output = CappedMultiset<U>;
+
+    /// `&a | &b` returns the [`union`](struct.CappedMultiset.html#method.union)
+    /// of `a` and `b`.
+    fn bitor(self, other: Self) -> CappedMultiset<U> {
+        self.union(other)
+    }
+}
+
+impl<U> std::ops::BitAnd for &CappedMultiset<U>
+where
+    U: Ord + Clone,
+{
+    type Output = CappedMultiset<U>;
+
+    /// `&a & &b` returns the
+    /// [`intersection`](struct.CappedMultiset.html#method.intersection) of
+    /// `a` and `b`.
+    fn bitand(self, other: Self) -> CappedMultiset<U> {
+        self.intersection(other)
+    }
+}
+
+impl<U> std::ops::Sub for &CappedMultiset<U>
+where
+    U: Ord + Clone,
+{
+    type Output = CappedMultiset<U>;
+
+    /// `&a - &b` returns the
+    /// [`difference`](struct.CappedMultiset.html#method.difference) of `a`
+    /// and `b`.
+    fn sub(self, other: Self) -> CappedMultiset<U> {
+        self.difference(other)
+    }
+}
+
+impl<U> std::ops::BitXor for &CappedMultiset<U>
+where
+    U: Ord + Clone,
+{
+    type Output = CappedMultiset<U>;
+
+    /// `&a ^ &b` returns the
+    /// [`symmetric_difference`](struct.CappedMultiset.html#method.symmetric_difference)
+    /// of `a` and `b`.
+    fn bitxor(self, other: Self) -> CappedMultiset<U> {
+        self.symmetric_difference(other)
+    }
+}
+
+impl<U> std::ops::Add for &CappedMultiset<U>
+where
+    U: Ord + Clone,
+{
+    type Output = CappedMultiset<U>;
+
+    /// `&a + &b` returns the [`sum_with`](struct.CappedMultiset.html#method.sum_with)
+    /// additive union of `a` and `b`.
+    fn add(self, other: Self) -> CappedMultiset<U> {
+        self.sum_with(other)
+    }
+}
+
+/// An iterator over the `(&U, usize)` pairs of a `CappedMultiset`, with the
+/// `usize` honoring the `cap` in effect when the iterator was created. See
+/// [`CappedMultiset::iter`](struct.CappedMultiset.html#method.iter).
+#[derive(Debug, Clone)]
+pub struct Iter<'a, U: 'a> {
+    /// The underlying `BTreeMap` iterator over raw stored counts.
+    inner: btree_map::Iter<'a, U, usize>,
+    /// The `cap` in effect when this iterator was created.
+    cap: Option<usize>,
+}
+
+impl<'a, U> Iterator for Iter<'a, U> {
+    type Item = (&'a U, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(elem, count)| {
+            let capped = match self.cap {
+                None => *count,
+                Some(c) => std::cmp::min(*count, c),
+            };
+            (elem, capped)
+        })
+    }
+}
+
+/// A consuming iterator over the `(U, usize)` pairs of a `CappedMultiset`,
+/// with the `usize` honoring the `cap` in effect when the Multiset was
+/// consumed. See the `IntoIterator` implementation for `CappedMultiset`.
+#[derive(Debug)]
+pub struct IntoIter<U> {
+    /// The underlying `BTreeMap` iterator over raw stored counts.
+    inner: btree_map::IntoIter<U, usize>,
+    /// The `cap` in effect when this iterator was created.
+    cap: Option<usize>,
+}
+
+impl<U> Iterator for IntoIter<U> {
+    type Item = (U, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(elem, count)| {
+            let capped = match self.cap {
+                None => count,
+                Some(c) => std::cmp::min(count, c),
+            };
+            (elem, capped)
+        })
+    }
+}
+
+/// An iterator that flattens a `CappedMultiset` into its individual
+/// elements, yielding each element cloned `capped_val(count)` times. See
+/// [`CappedMultiset::iter_expanded`](struct.CappedMultiset.html#method.iter_expanded).
+#[derive(Debug, Clone)]
+pub struct IterExpanded<'a, U: 'a> {
+    /// The underlying `BTreeMap` iterator over raw stored counts.
+    inner: btree_map::Iter<'a, U, usize>,
+    /// The `cap` in effect when this iterator was created.
+    cap: Option<usize>,
+    /// The element currently being expanded, along with how many clones of
+    /// it remain to be yielded.
+    current: Option<(&'a U, usize)>,
+}
+
+impl<'a, U> Iterator for IterExpanded<'a, U>
+where
+    U: Clone,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        loop {
+            if let Some((elem, remaining)) = self.current {
+                if remaining > 0 {
+                    self.current = Some((elem, remaining - 1));
+                    return Some(elem.clone());
+                }
+            }
+            match self.inner.next() {
+                None => return None,
+                Some((elem, count)) => {
+                    let capped = match self.cap {
+                        None => *count,
+                        Some(c) => std::cmp::min(*count, c),
+                    };
+                    self.current = Some((elem, capped));
+                }
+            }
+        }
+    }
+}
+
+impl<U> IntoIterator for CappedMultiset<U>
+where
+    U: Ord,
+{
+    type Item = (U, usize);
+    type IntoIter = IntoIter<U>;
+
+    fn into_iter(self) -> IntoIter<U> {
+        IntoIter {
+            inner: self.elements.into_iter(),
+            cap: self.cap,
+        }
+    }
+}
+
+impl<'a, U> IntoIterator for &'a CappedMultiset<U>
+where
+    U: Ord,
+{
+    type Item = (&'a U, usize);
+    type IntoIter = Iter<'a, U>;
+
+    fn into_iter(self) -> Iter<'a, U> {
+        self.iter()
+    }
+}
+
+impl<U> FromIterator<U> for CappedMultiset<U>
+where
+    U: Ord,
+{
+    /// Builds a `CappedMultiset` from a stream of elements, accumulating
+    /// duplicates into counts. The resulting `cap` defaults to `None`.
+    fn from_iter<I: IntoIterator<Item = U>>(iter: I) -> Self {
+        let mut mset = CappedMultiset::new(None);
+        for elem in iter {
+            mset.insert(elem);
+        }
+        mset
+    }
+}
+
+impl<U> FromIterator<(U, usize)> for CappedMultiset<U>
+where
+    U: Ord,
+{
+    /// Builds a `CappedMultiset` from a stream of `(element, count)` pairs,
+    /// summing counts for duplicate elements. The resulting `cap` defaults
+    /// to `None`.
+    fn from_iter<I: IntoIterator<Item = (U, usize)>>(iter: I) -> Self {
+        let mut mset = CappedMultiset::new(None);
+        for (elem, n) in iter {
+            mset.insert_multiple(elem, n);
+        }
+        mset
+    }
+}
+
+/// An iterator over the `(U, usize)` pairs removed by
+/// [`CappedMultiset::extract_if`](struct.CappedMultiset.html#method.extract_if).
+/// The matching entries have already been removed from the Multiset by the
+/// time this iterator is returned; driving it only yields the entries that
+/// were taken out, it does not remove any further ones.
+#[derive(Debug)]
+pub struct ExtractIf<U> {
+    /// The already-removed `(element, capped count)` pairs.
+    inner: std::vec::IntoIter<(U, usize)>,
+}
+
+impl<U> Iterator for ExtractIf<U> {
+    type Item = (U, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CappedMultiset;
+
+    /// Regression test for a larger multiset than the doctests exercise, to
+    /// guard against overflow or clamping mistakes in `sum`'s fold.
+    #[test]
+    fn sum_matches_scalar_expectation_for_a_large_multiset() {
+        let mut mset: CappedMultiset<u32> = CappedMultiset::new(Some(3));
+        for i in 0..200u32 {
+            mset.insert_multiple(i, (i % 7) as usize);
+        }
+        let expected: usize = (0..200u32)
+            .map(|i| std::cmp::min((i % 7) as usize, 3))
+            .sum();
+        assert_eq!(expected, mset.sum());
+    }
+}